@@ -0,0 +1,200 @@
+use crate::rmq::QueueInfo;
+use crate::triggers::Trigger;
+use anyhow::{anyhow, Result};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tiny_http::{Method, Response, Server};
+
+/// Latest poll result, shared with the slash-command server so it can answer
+/// instantly instead of waiting on the next poll.
+#[derive(Default)]
+pub struct QuerySnapshot {
+    queue_info: Mutex<Vec<QueueInfo>>,
+}
+
+impl QuerySnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&self, queue_info: Vec<QueueInfo>) {
+        *self.queue_info.lock().unwrap() = queue_info;
+    }
+}
+
+/// Spins up the HTTP endpoint that handles Slack slash commands such as
+/// `/rmq status` and `/rmq queue <name>`.
+pub fn serve(
+    snapshot: Arc<QuerySnapshot>,
+    triggers: Arc<Vec<Trigger>>,
+    listen_address: &str,
+) -> Result<()> {
+    let server = Server::http(listen_address)
+        .map_err(|e| anyhow!("failed to bind slash command server on {}: {}", listen_address, e))?;
+
+    thread::Builder::new()
+        .name("slash-command-server".into())
+        .spawn(move || {
+            for mut request in server.incoming_requests() {
+                if *request.method() != Method::Post {
+                    let _ = request.respond(
+                        Response::from_string("only POST is supported").with_status_code(405),
+                    );
+                    continue;
+                }
+
+                let mut body = String::new();
+                if let Err(e) = request.as_reader().read_to_string(&mut body) {
+                    log::warn!("Failed to read slash command body: {}", e);
+                    let _ = request
+                        .respond(Response::from_string("bad request").with_status_code(400));
+                    continue;
+                }
+
+                let command_text = parse_command_text(&body);
+                let reply = handle_command(&command_text, &snapshot, &triggers);
+                if let Err(e) = request.respond(Response::from_string(reply)) {
+                    log::warn!("Failed to respond to slash command: {}", e);
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+/// Slack posts slash commands as `application/x-www-form-urlencoded`; we only
+/// care about the `text` field.
+fn parse_command_text(body: &str) -> String {
+    body.split('&')
+        .find_map(|pair| pair.strip_prefix("text="))
+        .map(url_decode)
+        .unwrap_or_default()
+}
+
+/// Decodes `application/x-www-form-urlencoded` percent-escapes. Works on raw
+/// bytes and only assembles the result into a `String` at the end, so a
+/// multi-byte UTF-8 character spread across several `%XX` escapes (e.g. a
+/// non-ASCII queue name) comes back correctly instead of being mangled byte
+/// by byte.
+fn url_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            b'%' if i + 2 < bytes.len() => {
+                let hi = (bytes[i + 1] as char).to_digit(16);
+                let lo = (bytes[i + 2] as char).to_digit(16);
+                match (hi, lo) {
+                    (Some(hi), Some(lo)) => {
+                        decoded.push((hi * 16 + lo) as u8);
+                        i += 3;
+                    }
+                    _ => {
+                        decoded.push(b'%');
+                        i += 1;
+                    }
+                }
+            }
+            other => {
+                decoded.push(other);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_default()
+}
+
+fn handle_command(text: &str, snapshot: &QuerySnapshot, triggers: &[Trigger]) -> String {
+    let mut parts = text.trim().splitn(2, ' ');
+    match parts.next().unwrap_or("") {
+        "queue" => {
+            let queue_name = parts.next().unwrap_or("").trim();
+            if queue_name.is_empty() {
+                "Usage: /rmq queue <name>".to_string()
+            } else {
+                format_queue_status(queue_name, snapshot, triggers)
+            }
+        }
+        "status" | "" => format_overall_status(snapshot, triggers),
+        other => format!("Unknown command `{}`. Try `status` or `queue <name>`.", other),
+    }
+}
+
+fn format_queue_status(queue_name: &str, snapshot: &QuerySnapshot, triggers: &[Trigger]) -> String {
+    let queue_info = snapshot.queue_info.lock().unwrap();
+    let stats: Vec<&QueueInfo> = queue_info.iter().filter(|qi| qi.name == queue_name).collect();
+    if stats.is_empty() {
+        return format!("No data for queue {} yet.", queue_name);
+    }
+
+    let mut lines = vec![format!("Queue {}:", queue_name)];
+    for qi in &stats {
+        lines.push(format!("  {} = {}", qi.stat.name, qi.stat.value));
+    }
+
+    for t in triggers {
+        if let Some(trigger_queue) = &t.data().queue {
+            if trigger_queue != queue_name {
+                continue;
+            }
+        }
+        if let Some(qi) = stats.iter().find(|qi| qi.stat.name == t.field_name()) {
+            let threshold = t.data().threshold;
+            let pct = if threshold > 0 {
+                (qi.stat.value as f64 / threshold as f64) * 100.0
+            } else {
+                0.0
+            };
+            lines.push(format!(
+                "  {} at {:.0}% of its {} threshold ({}/{})",
+                t.name(),
+                pct,
+                t.field_name(),
+                qi.stat.value,
+                threshold,
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn format_overall_status(snapshot: &QuerySnapshot, triggers: &[Trigger]) -> String {
+    let queue_info = snapshot.queue_info.lock().unwrap();
+    if queue_info.is_empty() {
+        return "No queue data yet, still waiting on the first poll.".to_string();
+    }
+
+    let mut queue_names: Vec<&str> = queue_info.iter().map(|qi| qi.name.as_str()).collect();
+    queue_names.sort();
+    queue_names.dedup();
+
+    let mut lines = vec![format!("Tracking {} queue(s).", queue_names.len())];
+    for t in triggers {
+        for qi in queue_info.iter().filter(|qi| qi.stat.name == t.field_name()) {
+            if let Some(trigger_queue) = &t.data().queue {
+                if trigger_queue != &qi.name {
+                    continue;
+                }
+            }
+            let threshold = t.data().threshold;
+            if threshold > 0 && qi.stat.value as f64 >= threshold as f64 * 0.8 {
+                lines.push(format!(
+                    "  {} close to breaching {} ({}/{})",
+                    qi.name,
+                    t.name(),
+                    qi.stat.value,
+                    threshold,
+                ));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::SqlitePool;
+
+/// A currently-open breach of a `(queue_name, field_name)` trigger, tracked
+/// so we only re-notify on the clear -> breached transition.
+#[derive(Debug, Clone)]
+pub struct Incident {
+    pub queue_name: String,
+    pub field_name: String,
+    pub value: u64,
+    pub first_seen: String,
+}
+
+/// SQLite-backed store of open incidents, so alert state survives restarts.
+pub struct IncidentStore {
+    runtime: tokio::runtime::Runtime,
+    pool: SqlitePool,
+}
+
+impl IncidentStore {
+    pub fn new(database_path: &str) -> Result<Self> {
+        let runtime = tokio::runtime::Runtime::new()?;
+        let pool = runtime.block_on(async {
+            let opts = SqliteConnectOptions::new()
+                .filename(database_path)
+                .create_if_missing(true);
+            let pool = SqlitePool::connect_with(opts).await?;
+            sqlx::query(
+                "CREATE TABLE IF NOT EXISTS incidents (
+                    queue_name TEXT NOT NULL,
+                    field_name TEXT NOT NULL,
+                    value INTEGER NOT NULL,
+                    first_seen TEXT NOT NULL,
+                    PRIMARY KEY (queue_name, field_name)
+                )",
+            )
+            .execute(&pool)
+            .await?;
+            Ok::<_, anyhow::Error>(pool)
+        })?;
+
+        Ok(IncidentStore { runtime, pool })
+    }
+
+    /// Records a breaching value for `(queue_name, field_name)`. Returns
+    /// `true` if this is a new incident (clear -> breached), `false` if one
+    /// was already open and only its value was refreshed.
+    pub fn record_breach(&self, queue_name: &str, field_name: &str, value: u64) -> Result<bool> {
+        self.runtime.block_on(async {
+            let existing: Option<(i64,)> = sqlx::query_as(
+                "SELECT value FROM incidents WHERE queue_name = ? AND field_name = ?",
+            )
+            .bind(queue_name)
+            .bind(field_name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            if existing.is_some() {
+                sqlx::query("UPDATE incidents SET value = ? WHERE queue_name = ? AND field_name = ?")
+                    .bind(value as i64)
+                    .bind(queue_name)
+                    .bind(field_name)
+                    .execute(&self.pool)
+                    .await?;
+                Ok(false)
+            } else {
+                sqlx::query(
+                    "INSERT INTO incidents (queue_name, field_name, value, first_seen) VALUES (?, ?, ?, ?)",
+                )
+                .bind(queue_name)
+                .bind(field_name)
+                .bind(value as i64)
+                .bind(chrono::Utc::now().to_rfc3339())
+                .execute(&self.pool)
+                .await?;
+                Ok(true)
+            }
+        })
+    }
+
+    /// Clears the open incident for `(queue_name, field_name)`, if any.
+    /// Returns `true` if an incident was actually cleared.
+    pub fn resolve(&self, queue_name: &str, field_name: &str) -> Result<bool> {
+        self.runtime.block_on(async {
+            let result = sqlx::query("DELETE FROM incidents WHERE queue_name = ? AND field_name = ?")
+                .bind(queue_name)
+                .bind(field_name)
+                .execute(&self.pool)
+                .await?;
+            Ok(result.rows_affected() > 0)
+        })
+    }
+
+    /// All incidents currently tracked as open.
+    pub fn open_incidents(&self) -> Result<Vec<Incident>> {
+        self.runtime.block_on(async {
+            let rows: Vec<(String, String, i64, String)> = sqlx::query_as(
+                "SELECT queue_name, field_name, value, first_seen FROM incidents",
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|(queue_name, field_name, value, first_seen)| Incident {
+                    queue_name,
+                    field_name,
+                    value: value as u64,
+                    first_seen,
+                })
+                .collect())
+        })
+    }
+}
@@ -1,121 +1,96 @@
+mod commands;
+mod incidents;
+mod metrics;
+mod notifier;
+mod queue_source;
 mod rmq;
 mod slack;
+mod triggers;
 
 use anyhow::Result;
 use human_panic::setup_panic;
+use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::{thread, time};
 use structopt::StructOpt;
 use serde_derive::{Deserialize};
 use toml;
-use slack::{SlackMsg, send_multiple_slack_msgs};
-use rmq::{get_queue_info, QueueStat};
+use commands::QuerySnapshot;
+use incidents::IncidentStore;
+use metrics::Metrics;
+use notifier::stdout::StdoutNotifier;
+use notifier::{Alert, Notifier, NotifierConfig};
+use queue_source::{LiveQueueSource, MockQueueSource, QueueSource};
+use triggers::{check_trigger_applicability, Op, Trigger, TriggerEngine};
 
 #[derive(Debug, StructOpt)]
 struct Cli {
     /// Path to the config.toml
     #[structopt(long = "config", short = "c", default_value = "config.toml")]
     config_path: PathBuf,
+
+    /// Replay queue stats from a TOML/JSON fixture instead of a real broker,
+    /// and print alerts to stdout instead of sending them.
+    #[structopt(long = "dry-run")]
+    dry_run: Option<PathBuf>,
 }
 
 #[derive(Deserialize, Debug)]
 struct Config {
     rabbitmq: RabbitMqConfig,
     settings: MonitorSettings,
-    slack: SlackConfig,
     triggers: Vec<Trigger>,
+    notifier: Vec<NotifierConfig>,
+    prometheus: Option<PrometheusConfig>,
+    persistence: Option<PersistenceConfig>,
+    bot: Option<BotConfig>,
 }
 
 #[derive(Deserialize, Debug)]
-struct RabbitMqConfig {
-    protocol: String,
-    host: String,
-    username: String,
-    password: String,
-    port: String,
-    vhost: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct MonitorSettings {
-    poll_seconds: u64,
+struct PrometheusConfig {
+    listen_address: String,
 }
 
 #[derive(Deserialize, Debug)]
-struct SlackConfig {
-    webhook_url: String,
-    channel: String,
-    screen_name: String,
-    icon_url: Option<String>,
-    icon_emoji: Option<String>,
+struct BotConfig {
+    listen_address: String,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(tag = "type")]
-enum Trigger {
-    #[serde(rename = "consumers_total")]
-    ConsumersTotal(TriggerData),
-    
-    #[serde(rename = "memory_total")]
-    MemoryTotal(TriggerData),
-    
-    #[serde(rename = "messages_total")]
-    MessagesTotal(TriggerData),
-    
-    #[serde(rename = "messages_ready")]
-    ReadyMsgs(TriggerData),
-    
-    #[serde(rename = "messages_unacknowledged")]
-    UnacknowledgedMsgs(TriggerData),
+#[derive(Deserialize, Debug, Clone)]
+struct PersistenceConfig {
+    #[serde(default = "default_database_path")]
+    database_path: String,
 }
 
-impl Trigger {
-    fn data(&self) -> &TriggerData {
-        match self {
-            Trigger::ConsumersTotal(data) => data,
-            Trigger::MemoryTotal(data) => data,
-            Trigger::MessagesTotal(data) => data,
-            Trigger::ReadyMsgs(data) => data,
-            Trigger::UnacknowledgedMsgs(data) => data,
-        }
-    }
-
-    fn field_name(&self) -> &'static str {
-        match *self {
-            Trigger::ConsumersTotal(_) => "consumers",
-            Trigger::MemoryTotal(_) => "memory",
-            Trigger::MessagesTotal(_) => "messages",
-            Trigger::ReadyMsgs(_) => "messages_ready",
-            Trigger::UnacknowledgedMsgs(_) => "messages_unacknowledged",
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        PersistenceConfig {
+            database_path: default_database_path(),
         }
     }
+}
 
-    fn name(&self) -> &'static str {
-        match *self {
-            Trigger::ConsumersTotal(_) => "total number of consumers",
-            Trigger::MemoryTotal(_) => "memory consumption",
-            Trigger::MessagesTotal(_) => "total number of messages",
-            Trigger::ReadyMsgs(_) => "ready messages",
-            Trigger::UnacknowledgedMsgs(_) => "unacknowledged messages",
-        }
-    }
+fn default_database_path() -> String {
+    "rmq_monitor.sqlite".to_string()
 }
 
 #[derive(Deserialize, Debug)]
-struct TriggerData {
-    threshold: u64,
-    queue: Option<String>,
+struct RabbitMqConfig {
+    protocol: String,
+    host: String,
+    username: String,
+    password: String,
+    port: String,
+    vhost: String,
 }
 
 #[derive(Deserialize, Debug)]
-enum TriggerType {
-    Ready,
+struct MonitorSettings {
+    poll_seconds: u64,
 }
 
-type QueueName = str;
-type TriggerFieldname = str;
-
 fn main() -> Result<()> {
     setup_panic!();
     let args = Cli::from_args();
@@ -131,6 +106,49 @@ fn main() -> Result<()> {
     );
     log::debug!("Config loaded: {:?}", config);
 
+    let persistence_config = config.persistence.clone().unwrap_or_default();
+    let incident_store = IncidentStore::new(&persistence_config.database_path)?;
+
+    let metrics = Arc::new(Metrics::new()?);
+    if let Some(prometheus_config) = &config.prometheus {
+        log::info!(
+            "Serving Prometheus metrics on {}",
+            &prometheus_config.listen_address
+        );
+        metrics::serve(Arc::clone(&metrics), &prometheus_config.listen_address)?;
+    }
+
+    let queue_source: Box<dyn QueueSource> = match &args.dry_run {
+        Some(fixture_path) => {
+            log::info!("Dry run: replaying queue stats from {}", fixture_path.display());
+            Box::new(MockQueueSource::load(fixture_path)?)
+        }
+        None => Box::new(LiveQueueSource::new(
+            config.rabbitmq.protocol.clone(),
+            config.rabbitmq.host.clone(),
+            config.rabbitmq.port.clone(),
+            config.rabbitmq.username.clone(),
+            config.rabbitmq.password.clone(),
+        )),
+    };
+
+    let notifiers: Vec<Box<dyn Notifier>> = if args.dry_run.is_some() {
+        vec![Box::new(StdoutNotifier)]
+    } else {
+        config.notifier.iter().map(|c| c.build()).collect()
+    };
+    let mut trigger_engine = TriggerEngine::new();
+
+    let snapshot = Arc::new(QuerySnapshot::new());
+    if let Some(bot_config) = &config.bot {
+        log::info!(
+            "Serving Slack slash commands on {}",
+            &bot_config.listen_address
+        );
+        let triggers = Arc::new(config.triggers.clone());
+        commands::serve(Arc::clone(&snapshot), triggers, &bot_config.listen_address)?;
+    }
+
     let sleep_time = time::Duration::from_secs(config.settings.poll_seconds);
     loop {
         log::info!(
@@ -138,63 +156,103 @@ fn main() -> Result<()> {
             &config.rabbitmq.host,
             &config.rabbitmq.port
         );
-        let queue_info = get_queue_info(
-            &config.rabbitmq.protocol,
-            &config.rabbitmq.host,
-            &config.rabbitmq.port,
-            &config.rabbitmq.username,
-            &config.rabbitmq.password,
-        )?;
+        let queue_info = queue_source.fetch()?;
         log::debug!("Fetched queue info: {:?}", queue_info);
-        
-        let mut active_trigger_registry: Vec<(&QueueName, &TriggerFieldname)> = vec![];
-        let msgs: Vec<SlackMsg> = config.triggers.iter()
-            .map(|t| {
-                let msgs: Vec<SlackMsg> = queue_info.iter()
-                    .filter(|qi| check_trigger_applicability(t, &qi.name, &qi.stat))
-                    .filter(|qi| qi.stat.value > t.data().threshold)
-                    .map(|qi| {
-                        if active_trigger_registry.contains(&(&qi.name, t.field_name())) {
-                            return None;
-                        }
-                        active_trigger_registry.push((&qi.name, t.field_name()));
-                        Some(SlackMsg {
-                            username: config.slack.screen_name.clone(),
-                            channel: format!("#{}", &config.slack.channel),
-                            text: Some(format!("Queue {name} has passed a threshold of {threshold} {trigger_type}. Currently at {number}.", 
+        metrics.observe_queue_info(&queue_info);
+        snapshot.update(queue_info.clone());
+
+        let mut currently_breaching: HashSet<(String, String)> = HashSet::new();
+        let mut seen_trigger_keys: HashSet<(usize, String)> = HashSet::new();
+        let mut breach_alerts: Vec<Alert> = Vec::new();
+        for (trigger_index, t) in config.triggers.iter().enumerate() {
+            for qi in queue_info
+                .iter()
+                .filter(|qi| check_trigger_applicability(t, &qi.name, &qi.stat))
+            {
+                seen_trigger_keys.insert((trigger_index, qi.name.clone()));
+                if !trigger_engine.evaluate(trigger_index, t, qi) {
+                    continue;
+                }
+                currently_breaching.insert((qi.name.clone(), t.field_name().to_string()));
+                match incident_store.record_breach(&qi.name, t.field_name(), qi.stat.value) {
+                    Ok(true) => {
+                        metrics.record_trigger_fired();
+                        breach_alerts.push(Alert {
+                            queue_name: qi.name.clone(),
+                            field_name: t.field_name().to_string(),
+                            message: format!(
+                                "Queue {name} has {verb} a threshold of {threshold} {trigger_type}. Currently at {number}.",
                                 name = &qi.name,
+                                verb = t.data().op.breach_verb(),
                                 threshold = t.data().threshold,
                                 number = qi.stat.value,
                                 trigger_type = t.name(),
-                            )),
-                            icon_url: config.slack.icon_url.clone(),
-                            icon_emoji: config.slack.icon_emoji.clone(),
-                            attachments: None,
+                            ),
+                        });
+                    }
+                    Ok(false) => {}
+                    Err(e) => log::error!(
+                        "Failed to record incident for {}/{}: {}",
+                        &qi.name, t.field_name(), e
+                    ),
+                }
+            }
+        }
+        trigger_engine.end_poll(&seen_trigger_keys);
+
+        let mut resolution_alerts: Vec<Alert> = vec![];
+        match incident_store.open_incidents() {
+            Ok(open_incidents) => {
+                for incident in open_incidents {
+                    let key = (incident.queue_name.clone(), incident.field_name.clone());
+                    if currently_breaching.contains(&key) {
+                        continue;
+                    }
+                    let resolution_phrase = config
+                        .triggers
+                        .iter()
+                        .find(|t| {
+                            t.field_name() == incident.field_name
+                                && t.data().queue.as_deref().map_or(true, |q| q == incident.queue_name)
                         })
-                    })
-                    .filter_map(|v| v)
-                    .collect();
-                return msgs;
-            })
-            .flat_map(|msgs| msgs)
-            .collect();
-
-        send_multiple_slack_msgs(&config.slack.webhook_url, &msgs)?;
-        
-        active_trigger_registry.clear();
-        
+                        .map(|t| t.data().op.resolution_phrase())
+                        .unwrap_or_else(|| Op::default().resolution_phrase());
+                    match incident_store.resolve(&incident.queue_name, &incident.field_name) {
+                        Ok(true) => resolution_alerts.push(Alert {
+                            queue_name: incident.queue_name.clone(),
+                            field_name: incident.field_name.clone(),
+                            message: format!(
+                                "Queue {name} is {phrase} for {field}.",
+                                name = incident.queue_name,
+                                phrase = resolution_phrase,
+                                field = incident.field_name,
+                            ),
+                        }),
+                        Ok(false) => {}
+                        Err(e) => log::error!(
+                            "Failed to resolve incident for {}/{}: {}",
+                            incident.queue_name, incident.field_name, e
+                        ),
+                    }
+                }
+            }
+            Err(e) => log::error!("Failed to list open incidents: {}", e),
+        }
+
+        let alerts: Vec<Alert> = breach_alerts.into_iter().chain(resolution_alerts).collect();
+        for notifier in &notifiers {
+            if let Err(e) = notifier.send(&alerts) {
+                log::error!("Failed to send alerts via notifier: {}", e);
+                metrics.record_notify_send_failure();
+            }
+        }
+
+        metrics.record_poll();
+
         log::info!(
             "Check passed, sleeping for {}s",
             &config.settings.poll_seconds
         );
         thread::sleep(sleep_time);
     }
-}
-
-fn check_trigger_applicability(trigger: &Trigger, queue_name: &str, stat: &QueueStat) -> bool {
-    if let Some(trigger_queue_name) = &trigger.data().queue {
-        return trigger_queue_name == queue_name && trigger.field_name() == stat.name;
-    } else {
-        return trigger.field_name() == stat.name;
-    }
 }
\ No newline at end of file
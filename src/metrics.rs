@@ -0,0 +1,111 @@
+use crate::rmq::QueueInfo;
+use anyhow::Result;
+use prometheus::{Encoder, GaugeVec, IntCounter, Opts, Registry, TextEncoder};
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Method, Response, Server};
+
+/// Publishes queue stats and internal counters in Prometheus text format.
+pub struct Metrics {
+    registry: Registry,
+    queue_stat: GaugeVec,
+    polls_completed: IntCounter,
+    triggers_fired: IntCounter,
+    notify_send_failures: IntCounter,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+
+        let queue_stat = GaugeVec::new(
+            Opts::new("rmq_monitor_queue_stat", "Latest value of a queue stat field"),
+            &["queue", "field"],
+        )?;
+        let polls_completed = IntCounter::new(
+            "rmq_monitor_polls_completed_total",
+            "Number of poll iterations completed",
+        )?;
+        let triggers_fired = IntCounter::new(
+            "rmq_monitor_triggers_fired_total",
+            "Number of triggers that fired an alert",
+        )?;
+        let notify_send_failures = IntCounter::new(
+            "rmq_monitor_notify_send_failures_total",
+            "Number of failed attempts to send an alert via a notifier",
+        )?;
+
+        registry.register(Box::new(queue_stat.clone()))?;
+        registry.register(Box::new(polls_completed.clone()))?;
+        registry.register(Box::new(triggers_fired.clone()))?;
+        registry.register(Box::new(notify_send_failures.clone()))?;
+
+        Ok(Metrics {
+            registry,
+            queue_stat,
+            polls_completed,
+            triggers_fired,
+            notify_send_failures,
+        })
+    }
+
+    pub fn observe_queue_info(&self, queue_info: &[QueueInfo]) {
+        for qi in queue_info {
+            self.queue_stat
+                .with_label_values(&[&qi.name, &qi.stat.name])
+                .set(qi.stat.value as f64);
+        }
+    }
+
+    pub fn record_poll(&self) {
+        self.polls_completed.inc();
+    }
+
+    pub fn record_trigger_fired(&self) {
+        self.triggers_fired.inc();
+    }
+
+    pub fn record_notify_send_failure(&self) {
+        self.notify_send_failures.inc();
+    }
+
+    fn gather_text(&self) -> Result<Vec<u8>> {
+        let encoder = TextEncoder::new();
+        let mut buffer = Vec::new();
+        encoder.encode(&self.registry.gather(), &mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+/// Spins up a lightweight HTTP server on `listen_address` that serves the
+/// current metrics snapshot on `GET /metrics` and 404s everything else.
+pub fn serve(metrics: Arc<Metrics>, listen_address: &str) -> Result<()> {
+    let server = Server::http(listen_address)
+        .map_err(|e| anyhow::anyhow!("failed to bind metrics server on {}: {}", listen_address, e))?;
+
+    thread::Builder::new()
+        .name("metrics-server".into())
+        .spawn(move || {
+            for request in server.incoming_requests() {
+                if *request.method() != Method::Get || request.url() != "/metrics" {
+                    let _ = request
+                        .respond(Response::from_string("not found").with_status_code(404));
+                    continue;
+                }
+
+                let response = match metrics.gather_text() {
+                    Ok(buffer) => Response::from_data(buffer),
+                    Err(e) => {
+                        log::error!("Failed to gather metrics: {}", e);
+                        Response::from_string(format!("error gathering metrics: {}", e))
+                            .with_status_code(500)
+                    }
+                };
+                if let Err(e) = request.respond(response) {
+                    log::warn!("Failed to respond to metrics scrape: {}", e);
+                }
+            }
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use super::{Alert, Notifier};
+use anyhow::{anyhow, Result};
+use serde_derive::Deserialize;
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MatrixNotifierConfig {
+    pub homeserver_url: String,
+    pub access_token: String,
+    pub room_id: String,
+}
+
+/// Posts alerts as `m.room.message` events via the Matrix client-server API.
+pub struct MatrixNotifier {
+    config: MatrixNotifierConfig,
+    client: reqwest::blocking::Client,
+    txn_counter: AtomicU64,
+}
+
+impl MatrixNotifier {
+    pub fn new(config: MatrixNotifierConfig) -> Self {
+        MatrixNotifier {
+            config,
+            client: reqwest::blocking::Client::new(),
+            txn_counter: AtomicU64::new(0),
+        }
+    }
+
+    fn next_txn_id(&self) -> String {
+        let seq = self.txn_counter.fetch_add(1, Ordering::SeqCst);
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        format!("rmq_monitor-{}-{}", millis, seq)
+    }
+}
+
+impl Notifier for MatrixNotifier {
+    fn send(&self, alerts: &[Alert]) -> Result<()> {
+        for alert in alerts {
+            let url = format!(
+                "{}/_matrix/client/r0/rooms/{}/send/m.room.message/{}",
+                self.config.homeserver_url.trim_end_matches('/'),
+                self.config.room_id,
+                self.next_txn_id(),
+            );
+            let response = self
+                .client
+                .put(&url)
+                .bearer_auth(&self.config.access_token)
+                .json(&json!({
+                    "msgtype": "m.text",
+                    "body": alert.message,
+                }))
+                .send()?;
+
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "Matrix server returned {} for room {}",
+                    response.status(),
+                    self.config.room_id
+                ));
+            }
+        }
+        Ok(())
+    }
+}
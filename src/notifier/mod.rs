@@ -0,0 +1,44 @@
+pub mod matrix;
+pub mod slack;
+pub mod stdout;
+pub mod webhook;
+
+use anyhow::Result;
+use serde_derive::Deserialize;
+
+/// A single breach or resolution event ready to be handed to a notifier,
+/// independent of which backend ends up delivering it.
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub queue_name: String,
+    pub field_name: String,
+    pub message: String,
+}
+
+/// Destination rmq_monitor can fan an `Alert` out to.
+pub trait Notifier {
+    fn send(&self, alerts: &[Alert]) -> Result<()>;
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum NotifierConfig {
+    #[serde(rename = "slack")]
+    Slack(slack::SlackNotifierConfig),
+
+    #[serde(rename = "matrix")]
+    Matrix(matrix::MatrixNotifierConfig),
+
+    #[serde(rename = "webhook")]
+    Webhook(webhook::WebhookNotifierConfig),
+}
+
+impl NotifierConfig {
+    pub fn build(&self) -> Box<dyn Notifier> {
+        match self {
+            NotifierConfig::Slack(config) => Box::new(slack::SlackNotifier::new(config.clone())),
+            NotifierConfig::Matrix(config) => Box::new(matrix::MatrixNotifier::new(config.clone())),
+            NotifierConfig::Webhook(config) => Box::new(webhook::WebhookNotifier::new(config.clone())),
+        }
+    }
+}
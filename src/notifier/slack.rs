@@ -0,0 +1,40 @@
+use super::{Alert, Notifier};
+use crate::slack::{send_multiple_slack_msgs, SlackMsg};
+use anyhow::Result;
+use serde_derive::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SlackNotifierConfig {
+    pub webhook_url: String,
+    pub channel: String,
+    pub screen_name: String,
+    pub icon_url: Option<String>,
+    pub icon_emoji: Option<String>,
+}
+
+pub struct SlackNotifier {
+    config: SlackNotifierConfig,
+}
+
+impl SlackNotifier {
+    pub fn new(config: SlackNotifierConfig) -> Self {
+        SlackNotifier { config }
+    }
+}
+
+impl Notifier for SlackNotifier {
+    fn send(&self, alerts: &[Alert]) -> Result<()> {
+        let msgs: Vec<SlackMsg> = alerts
+            .iter()
+            .map(|alert| SlackMsg {
+                username: self.config.screen_name.clone(),
+                channel: format!("#{}", &self.config.channel),
+                text: Some(alert.message.clone()),
+                icon_url: self.config.icon_url.clone(),
+                icon_emoji: self.config.icon_emoji.clone(),
+                attachments: None,
+            })
+            .collect();
+        send_multiple_slack_msgs(&self.config.webhook_url, &msgs)
+    }
+}
@@ -0,0 +1,15 @@
+use super::{Alert, Notifier};
+use anyhow::Result;
+
+/// Prints alerts to stdout instead of delivering them; used for `--dry-run`
+/// so previews don't hit a real Slack/Matrix/webhook destination.
+pub struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    fn send(&self, alerts: &[Alert]) -> Result<()> {
+        for alert in alerts {
+            println!("[dry-run] {}/{}: {}", alert.queue_name, alert.field_name, alert.message);
+        }
+        Ok(())
+    }
+}
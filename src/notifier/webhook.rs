@@ -0,0 +1,41 @@
+use super::{Alert, Notifier};
+use anyhow::Result;
+use serde_derive::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WebhookNotifierConfig {
+    pub url: String,
+}
+
+/// Posts each alert as a small JSON document to an arbitrary HTTP endpoint.
+pub struct WebhookNotifier {
+    config: WebhookNotifierConfig,
+    client: reqwest::blocking::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: WebhookNotifierConfig) -> Self {
+        WebhookNotifier {
+            config,
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn send(&self, alerts: &[Alert]) -> Result<()> {
+        for alert in alerts {
+            self.client
+                .post(&self.config.url)
+                .json(&json!({
+                    "queue": alert.queue_name,
+                    "field": alert.field_name,
+                    "message": alert.message,
+                }))
+                .send()?
+                .error_for_status()?;
+        }
+        Ok(())
+    }
+}
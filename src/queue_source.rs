@@ -0,0 +1,162 @@
+use crate::rmq::{get_queue_info, QueueInfo, QueueStat};
+use anyhow::Result;
+use serde_derive::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Where rmq_monitor gets its queue stats from. The live RabbitMQ HTTP API
+/// and the `--dry-run` fixture replay are both implementations of this.
+pub(crate) trait QueueSource {
+    fn fetch(&self) -> Result<Vec<QueueInfo>>;
+}
+
+pub(crate) struct LiveQueueSource {
+    protocol: String,
+    host: String,
+    port: String,
+    username: String,
+    password: String,
+}
+
+impl LiveQueueSource {
+    pub(crate) fn new(
+        protocol: String,
+        host: String,
+        port: String,
+        username: String,
+        password: String,
+    ) -> Self {
+        LiveQueueSource {
+            protocol,
+            host,
+            port,
+            username,
+            password,
+        }
+    }
+}
+
+impl QueueSource for LiveQueueSource {
+    fn fetch(&self) -> Result<Vec<QueueInfo>> {
+        get_queue_info(&self.protocol, &self.host, &self.port, &self.username, &self.password)
+    }
+}
+
+/// A single queue's stats in a `--dry-run` fixture. Fields are optional so a
+/// fixture can model malformed/partial broker responses.
+#[derive(Deserialize, Debug)]
+struct FixtureQueue {
+    name: String,
+    messages: Option<u64>,
+    messages_ready: Option<u64>,
+    messages_unacknowledged: Option<u64>,
+    consumers: Option<u64>,
+    memory: Option<u64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Fixture {
+    queues: Vec<FixtureQueue>,
+}
+
+/// Replays queue stats from a TOML or JSON fixture file, so the
+/// trigger-and-notify pipeline can be exercised without a broker.
+pub(crate) struct MockQueueSource {
+    fixture: Fixture,
+}
+
+impl MockQueueSource {
+    pub(crate) fn load(fixture_path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(fixture_path)?;
+        let fixture: Fixture = match fixture_path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&contents)?,
+            _ => toml::from_str(&contents)?,
+        };
+        Ok(MockQueueSource { fixture })
+    }
+}
+
+impl QueueSource for MockQueueSource {
+    fn fetch(&self) -> Result<Vec<QueueInfo>> {
+        let mut queue_info = Vec::new();
+        for q in &self.fixture.queues {
+            let fields: [(&str, Option<u64>); 5] = [
+                ("messages", q.messages),
+                ("messages_ready", q.messages_ready),
+                ("messages_unacknowledged", q.messages_unacknowledged),
+                ("consumers", q.consumers),
+                ("memory", q.memory),
+            ];
+            for (field_name, value) in fields {
+                if let Some(value) = value {
+                    queue_info.push(QueueInfo {
+                        name: q.name.clone(),
+                        stat: QueueStat {
+                            name: field_name.to_string(),
+                            value,
+                        },
+                    });
+                }
+            }
+        }
+        Ok(queue_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_fixture_parses_partial_fields() {
+        let toml_src = r#"
+            [[queues]]
+            name = "orders"
+            messages = 5
+            consumers = 2
+        "#;
+        let fixture: Fixture = toml::from_str(toml_src).unwrap();
+        let source = MockQueueSource { fixture };
+        let queue_info = source.fetch().unwrap();
+
+        assert_eq!(queue_info.len(), 2);
+        assert!(queue_info
+            .iter()
+            .any(|qi| qi.name == "orders" && qi.stat.name == "messages" && qi.stat.value == 5));
+        assert!(queue_info
+            .iter()
+            .any(|qi| qi.name == "orders" && qi.stat.name == "consumers" && qi.stat.value == 2));
+    }
+
+    #[test]
+    fn json_fixture_parses_partial_fields() {
+        let json_src = r#"
+            {
+                "queues": [
+                    { "name": "payments", "memory": 1024 }
+                ]
+            }
+        "#;
+        let fixture: Fixture = serde_json::from_str(json_src).unwrap();
+        let source = MockQueueSource { fixture };
+        let queue_info = source.fetch().unwrap();
+
+        assert_eq!(queue_info.len(), 1);
+        assert_eq!(queue_info[0].name, "payments");
+        assert_eq!(queue_info[0].stat.name, "memory");
+        assert_eq!(queue_info[0].stat.value, 1024);
+    }
+
+    #[test]
+    fn fixture_with_no_fields_set_yields_no_queue_info() {
+        let toml_src = r#"
+            [[queues]]
+            name = "empty"
+        "#;
+        let fixture: Fixture = toml::from_str(toml_src).unwrap();
+        let source = MockQueueSource { fixture };
+        let queue_info = source.fetch().unwrap();
+
+        assert!(queue_info.is_empty());
+    }
+}
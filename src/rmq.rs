@@ -0,0 +1,80 @@
+use anyhow::Result;
+use serde_derive::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct QueueStat {
+    pub name: String,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueueInfo {
+    pub name: String,
+    pub stat: QueueStat,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawQueue {
+    name: String,
+    messages: u64,
+    messages_ready: u64,
+    messages_unacknowledged: u64,
+    consumers: u64,
+    memory: u64,
+}
+
+pub fn get_queue_info(
+    protocol: &str,
+    host: &str,
+    port: &str,
+    username: &str,
+    password: &str,
+) -> Result<Vec<QueueInfo>> {
+    let url = format!("{}://{}:{}/api/queues", protocol, host, port);
+    let raw_queues: Vec<RawQueue> = reqwest::blocking::Client::new()
+        .get(&url)
+        .basic_auth(username, Some(password))
+        .send()?
+        .json()?;
+
+    let mut queue_info = Vec::new();
+    for raw in raw_queues {
+        queue_info.push(QueueInfo {
+            name: raw.name.clone(),
+            stat: QueueStat {
+                name: "messages".to_string(),
+                value: raw.messages,
+            },
+        });
+        queue_info.push(QueueInfo {
+            name: raw.name.clone(),
+            stat: QueueStat {
+                name: "messages_ready".to_string(),
+                value: raw.messages_ready,
+            },
+        });
+        queue_info.push(QueueInfo {
+            name: raw.name.clone(),
+            stat: QueueStat {
+                name: "messages_unacknowledged".to_string(),
+                value: raw.messages_unacknowledged,
+            },
+        });
+        queue_info.push(QueueInfo {
+            name: raw.name.clone(),
+            stat: QueueStat {
+                name: "consumers".to_string(),
+                value: raw.consumers,
+            },
+        });
+        queue_info.push(QueueInfo {
+            name: raw.name,
+            stat: QueueStat {
+                name: "memory".to_string(),
+                value: raw.memory,
+            },
+        });
+    }
+
+    Ok(queue_info)
+}
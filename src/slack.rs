@@ -0,0 +1,20 @@
+use anyhow::Result;
+use serde_derive::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SlackMsg {
+    pub username: String,
+    pub channel: String,
+    pub text: Option<String>,
+    pub icon_url: Option<String>,
+    pub icon_emoji: Option<String>,
+    pub attachments: Option<Vec<String>>,
+}
+
+pub fn send_multiple_slack_msgs(webhook_url: &str, msgs: &[SlackMsg]) -> Result<()> {
+    let client = reqwest::blocking::Client::new();
+    for msg in msgs {
+        client.post(webhook_url).json(msg).send()?;
+    }
+    Ok(())
+}
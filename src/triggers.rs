@@ -0,0 +1,294 @@
+use crate::rmq::{QueueInfo, QueueStat};
+use serde_derive::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Instant;
+
+/// Comparison used to decide whether a trigger's threshold is breached.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Op {
+    Gt,
+    Lt,
+    Eq,
+}
+
+impl Default for Op {
+    fn default() -> Self {
+        Op::Gt
+    }
+}
+
+impl Op {
+    /// Verb describing a breach in the direction this operator fires, e.g.
+    /// "Queue X has {verb} a threshold of ...".
+    pub(crate) fn breach_verb(&self) -> &'static str {
+        match self {
+            Op::Gt => "passed",
+            Op::Lt => "dropped below",
+            Op::Eq => "reached",
+        }
+    }
+
+    /// Phrase describing the condition clearing, e.g. "Queue X is {phrase}".
+    pub(crate) fn resolution_phrase(&self) -> &'static str {
+        match self {
+            Op::Gt => "back under threshold",
+            Op::Lt => "back above threshold",
+            Op::Eq => "no longer at threshold",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub(crate) enum Trigger {
+    #[serde(rename = "consumers_total")]
+    ConsumersTotal(TriggerData),
+
+    #[serde(rename = "memory_total")]
+    MemoryTotal(TriggerData),
+
+    #[serde(rename = "messages_total")]
+    MessagesTotal(TriggerData),
+
+    #[serde(rename = "messages_ready")]
+    ReadyMsgs(TriggerData),
+
+    #[serde(rename = "messages_unacknowledged")]
+    UnacknowledgedMsgs(TriggerData),
+}
+
+impl Trigger {
+    pub(crate) fn data(&self) -> &TriggerData {
+        match self {
+            Trigger::ConsumersTotal(data) => data,
+            Trigger::MemoryTotal(data) => data,
+            Trigger::MessagesTotal(data) => data,
+            Trigger::ReadyMsgs(data) => data,
+            Trigger::UnacknowledgedMsgs(data) => data,
+        }
+    }
+
+    pub(crate) fn field_name(&self) -> &'static str {
+        match *self {
+            Trigger::ConsumersTotal(_) => "consumers",
+            Trigger::MemoryTotal(_) => "memory",
+            Trigger::MessagesTotal(_) => "messages",
+            Trigger::ReadyMsgs(_) => "messages_ready",
+            Trigger::UnacknowledgedMsgs(_) => "messages_unacknowledged",
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match *self {
+            Trigger::ConsumersTotal(_) => "total number of consumers",
+            Trigger::MemoryTotal(_) => "memory consumption",
+            Trigger::MessagesTotal(_) => "total number of messages",
+            Trigger::ReadyMsgs(_) => "ready messages",
+            Trigger::UnacknowledgedMsgs(_) => "unacknowledged messages",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct TriggerData {
+    #[serde(default)]
+    pub(crate) op: Op,
+    pub(crate) threshold: u64,
+    pub(crate) queue: Option<String>,
+    /// Sustained mode: only fire once the condition has held for this many
+    /// consecutive polls.
+    pub(crate) for_polls: Option<u64>,
+    /// Rate-of-change mode: fire once the value is changing faster than this
+    /// many units per second, over `rate_window_secs`.
+    pub(crate) rate_per_sec: Option<f64>,
+    #[serde(default = "default_rate_window_secs")]
+    pub(crate) rate_window_secs: u64,
+}
+
+fn default_rate_window_secs() -> u64 {
+    60
+}
+
+impl TriggerData {
+    fn compares(&self, value: u64) -> bool {
+        match self.op {
+            Op::Gt => value > self.threshold,
+            Op::Lt => value < self.threshold,
+            Op::Eq => value == self.threshold,
+        }
+    }
+}
+
+pub(crate) fn check_trigger_applicability(
+    trigger: &Trigger,
+    queue_name: &str,
+    stat: &QueueStat,
+) -> bool {
+    if let Some(trigger_queue_name) = &trigger.data().queue {
+        trigger_queue_name == queue_name && trigger.field_name() == stat.name
+    } else {
+        trigger.field_name() == stat.name
+    }
+}
+
+struct SampleWindow {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+/// Tracks the per-(trigger, queue) state that sustained-duration and
+/// rate-of-change trigger modes need across poll iterations.
+#[derive(Default)]
+pub(crate) struct TriggerEngine {
+    sustained_counts: HashMap<(usize, String), u64>,
+    rate_windows: HashMap<(usize, String), SampleWindow>,
+}
+
+impl TriggerEngine {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluates whether `trigger` (identified by its index in the config's
+    /// trigger list) should fire for `qi` on this poll.
+    pub(crate) fn evaluate(&mut self, trigger_index: usize, trigger: &Trigger, qi: &QueueInfo) -> bool {
+        let data = trigger.data();
+        let breaching = data.compares(qi.stat.value);
+        let key = (trigger_index, qi.name.clone());
+
+        if let Some(for_polls) = data.for_polls {
+            let count = self.sustained_counts.entry(key).or_insert(0);
+            if breaching {
+                *count += 1;
+            } else {
+                *count = 0;
+            }
+            return *count >= for_polls;
+        }
+
+        if let Some(rate_per_sec) = data.rate_per_sec {
+            let window = self
+                .rate_windows
+                .entry(key)
+                .or_insert_with(|| SampleWindow {
+                    samples: VecDeque::new(),
+                });
+            let now = Instant::now();
+            window.samples.push_back((now, qi.stat.value));
+
+            while let Some((oldest, _)) = window.samples.front() {
+                if now.duration_since(*oldest).as_secs() > data.rate_window_secs {
+                    window.samples.pop_front();
+                } else {
+                    break;
+                }
+            }
+
+            if window.samples.len() < 2 {
+                return false;
+            }
+
+            let (oldest_instant, oldest_value) = *window.samples.front().unwrap();
+            let (newest_instant, newest_value) = *window.samples.back().unwrap();
+            let elapsed = newest_instant.duration_since(oldest_instant).as_secs_f64();
+            if elapsed <= 0.0 {
+                return false;
+            }
+
+            let slope = (newest_value as f64 - oldest_value as f64) / elapsed;
+            return slope > rate_per_sec;
+        }
+
+        breaching
+    }
+
+    /// Drops sustained/rate-of-change state for any `(trigger, queue)` key
+    /// not present in `seen` this poll, so a queue that briefly disappears
+    /// from the broker's response doesn't keep stale state alive, and so a
+    /// sustained-mode counter actually measures *consecutive* breaching
+    /// polls rather than resuming after a gap.
+    pub(crate) fn end_poll(&mut self, seen: &HashSet<(usize, String)>) {
+        self.sustained_counts.retain(|key, _| seen.contains(key));
+        self.rate_windows.retain(|key, _| seen.contains(key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qi(name: &str, field: &str, value: u64) -> QueueInfo {
+        QueueInfo {
+            name: name.to_string(),
+            stat: QueueStat {
+                name: field.to_string(),
+                value,
+            },
+        }
+    }
+
+    fn sustained_trigger(for_polls: u64) -> Trigger {
+        Trigger::MessagesTotal(TriggerData {
+            op: Op::Gt,
+            threshold: 10,
+            queue: None,
+            for_polls: Some(for_polls),
+            rate_per_sec: None,
+            rate_window_secs: default_rate_window_secs(),
+        })
+    }
+
+    fn rate_trigger(rate_per_sec: f64) -> Trigger {
+        Trigger::MessagesTotal(TriggerData {
+            op: Op::Gt,
+            threshold: 10,
+            queue: None,
+            for_polls: None,
+            rate_per_sec: Some(rate_per_sec),
+            rate_window_secs: default_rate_window_secs(),
+        })
+    }
+
+    #[test]
+    fn sustained_mode_requires_consecutive_breaching_polls() {
+        let mut engine = TriggerEngine::new();
+        let trigger = sustained_trigger(2);
+
+        assert!(!engine.evaluate(0, &trigger, &qi("orders", "messages", 20)));
+        assert!(engine.evaluate(0, &trigger, &qi("orders", "messages", 20)));
+    }
+
+    #[test]
+    fn end_poll_resets_sustained_counter_for_missing_queue() {
+        let mut engine = TriggerEngine::new();
+        let trigger = sustained_trigger(2);
+
+        engine.evaluate(0, &trigger, &qi("orders", "messages", 20));
+        // "orders" is absent from this poll (e.g. broker hiccup).
+        engine.end_poll(&HashSet::new());
+
+        // The next breaching poll should start counting from 1 again, not
+        // resume at 2 and fire immediately.
+        assert!(!engine.evaluate(0, &trigger, &qi("orders", "messages", 20)));
+    }
+
+    #[test]
+    fn end_poll_evicts_state_for_queues_not_seen() {
+        let mut engine = TriggerEngine::new();
+        let trigger = sustained_trigger(1);
+        engine.evaluate(0, &trigger, &qi("orders", "messages", 20));
+
+        assert_eq!(engine.sustained_counts.len(), 1);
+        engine.end_poll(&HashSet::new());
+        assert_eq!(engine.sustained_counts.len(), 0);
+    }
+
+    #[test]
+    fn rate_mode_guards_against_a_single_sample() {
+        let mut engine = TriggerEngine::new();
+        let trigger = rate_trigger(1.0);
+
+        // With only one sample there's no slope to compute yet.
+        assert!(!engine.evaluate(0, &trigger, &qi("orders", "messages", 5)));
+    }
+}